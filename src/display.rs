@@ -0,0 +1,58 @@
+//! Zero-allocation `Display` adapter for zbase32 encoding.
+
+#[cfg(feature = "std")]
+use std::fmt::{self, Write};
+#[cfg(not(feature = "std"))]
+use core::fmt::{self, Write};
+
+use encode_with;
+
+/// Wraps a byte slice so it formats as its zbase32 encoding.
+///
+/// Unlike `encode`, writing a `Display` straight into a `fmt::Write` sink (a
+/// `String`, `println!`, a socket, ...) never allocates an intermediate
+/// `Vec<u8>`. Created with `display`.
+pub struct Display<'a> {
+    data: &'a [u8],
+}
+
+/// Wrap `data` so it formats as its zbase32 encoding.
+///
+/// # Examples
+///
+/// ```
+/// use zbase32;
+///
+/// assert_eq!(format!("{}", zbase32::display(b"testdata")), "qt1zg7drcf4gn");
+/// ```
+pub fn display<'a>(data: &'a [u8]) -> Display<'a> {
+    Display { data }
+}
+
+impl<'a> fmt::Display for Display<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bits = self.data.len() as u64 * 8;
+        let mut result = Ok(());
+        encode_with(self.data, bits, |symbol| {
+            if result.is_ok() {
+                result = f.write_char(char::from(symbol));
+            }
+        });
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(format!("{}", display(b"testdata")), "qt1zg7drcf4gn");
+    }
+
+    #[test]
+    fn test_display_empty() {
+        assert_eq!(format!("{}", display(b"")), "");
+    }
+}
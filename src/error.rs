@@ -0,0 +1,62 @@
+//! Error types returned by this crate's fallible functions.
+
+#[cfg(feature = "std")]
+use std::error;
+#[cfg(feature = "std")]
+use std::fmt;
+
+#[cfg(not(feature = "std"))]
+use core::fmt;
+
+/// Errors that can occur while encoding or decoding zbase32 data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The input contained a byte that is not a valid zbase32 digit.
+    InvalidDigit {
+        /// Byte offset of the offending digit within the input.
+        index: usize,
+        /// The offending byte.
+        byte: u8,
+    },
+    /// The destination buffer was too small to hold the result.
+    BufferTooShort {
+        /// Number of bytes required.
+        needed: usize,
+        /// Number of bytes actually available.
+        got: usize,
+    },
+    /// The input didn't contain enough bits for the requested bit count.
+    InputTooShort {
+        /// Number of bits requested.
+        needed: u64,
+        /// Number of bits actually available in the input.
+        got: u64,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::InvalidDigit { index, byte } => {
+                write!(f, "invalid zbase32 digit {:#04x} at index {}", byte, index)
+            }
+            Error::BufferTooShort { needed, got } => {
+                write!(f, "buffer too short: needed {} bytes, got {}", needed, got)
+            }
+            Error::InputTooShort { needed, got } => {
+                write!(f, "input too short: needed {} bits, got {}", needed, got)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for Error {
+    fn description(&self) -> &str {
+        match *self {
+            Error::InvalidDigit { .. } => "invalid zbase32 digit",
+            Error::BufferTooShort { .. } => "buffer too short",
+            Error::InputTooShort { .. } => "input too short",
+        }
+    }
+}
@@ -2,7 +2,17 @@
 //!
 //! This is an implementation of the human-oriented base-32 encoding called
 //! [zbase32](https://philzimmermann.com/docs/human-oriented-base-32-encoding.txt).
-
+//!
+//! The `std` feature is enabled by default. Build with `default-features =
+//! false` for a `no_std` build. With no other features enabled, this only
+//! gets you the allocation-free core: `encode_slice`/`decode_slice`,
+//! `decode_in_place`, `validate` and the `Display` adapter, with no
+//! allocator required at all. Enable `alloc` on top of that for the
+//! `Vec`/`String`-returning `encode`/`decode` family, or `std` (the default)
+//! for all of that plus the `read`/`write` streaming adapters, which need
+//! `std::io`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
 #![cfg_attr(feature="clippy", feature(plugin))]
 #![cfg_attr(feature="clippy", plugin(clippy))]
 #![cfg_attr(all(test, feature = "unstable"), feature(test))]
@@ -30,100 +40,206 @@
 #![cfg_attr(feature = "clippy", deny(wrong_pub_self_convention))]
 #![cfg_attr(feature = "clippy", deny(wrong_self_convention))]
 
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+#[macro_use]
+extern crate alloc;
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::string::String;
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::vec::Vec;
 
 /// Alphabet used by zbase32
 pub const ALPHABET: &[u8; 32] = b"ybndrfg8ejkmcpqxot1uwisza345h769";
 
-const CONV_ERR: Result<u8, &str> = Err("not a zbase32 digit");
+mod display;
+mod error;
+#[cfg(feature = "std")]
+pub mod read;
+#[cfg(all(feature = "simd", feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+mod simd;
+#[cfg(feature = "std")]
+pub mod write;
+
+pub use display::{display, Display};
+pub use error::Error;
 
 #[cfg_attr(rustfmt, rustfmt_skip)]
-const CONVERSION_TABLE: &[Result<u8, &str>; 256] = &[
-    /*   0 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /*   5 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /*  10 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /*  15 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /*  20 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /*  25 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /*  30 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /*  35 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /*  40 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /*  45 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, Ok(0x12),
-    /*  50 */ CONV_ERR, Ok(0x19), Ok(0x1a), Ok(0x1b), Ok(0x1e),
-    /*  55 */ Ok(0x1d), Ok(0x07), Ok(0x1f), CONV_ERR, CONV_ERR,
-    /*  60 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /*  65 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /*  70 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /*  75 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /*  80 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /*  85 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /*  90 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /*  95 */ CONV_ERR, CONV_ERR, Ok(0x18), Ok(0x01), Ok(0x0c),
-    /* 100 */ Ok(0x03), Ok(0x08), Ok(0x05), Ok(0x06), Ok(0x1c),
-    /* 105 */ Ok(0x15), Ok(0x09), Ok(0x0a), CONV_ERR, Ok(0x0b),
-    /* 110 */ Ok(0x02), Ok(0x10), Ok(0x0d), Ok(0x0e), Ok(0x04),
-    /* 115 */ Ok(0x16), Ok(0x11), Ok(0x13), CONV_ERR, Ok(0x14),
-    /* 120 */ Ok(0x0f), Ok(0x00), Ok(0x17), CONV_ERR, CONV_ERR,
-    /* 125 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 130 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 135 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 140 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 145 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 150 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 155 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 160 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 165 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 170 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 175 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 180 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 185 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 190 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 195 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 200 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 205 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 210 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 215 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 220 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 225 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 230 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 235 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 240 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 245 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 250 */ CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR, CONV_ERR,
-    /* 255 */ CONV_ERR
+const CONVERSION_TABLE: &[Option<u8>; 256] = &[
+    /*   0 */ None, None, None, None, None,
+    /*   5 */ None, None, None, None, None,
+    /*  10 */ None, None, None, None, None,
+    /*  15 */ None, None, None, None, None,
+    /*  20 */ None, None, None, None, None,
+    /*  25 */ None, None, None, None, None,
+    /*  30 */ None, None, None, None, None,
+    /*  35 */ None, None, None, None, None,
+    /*  40 */ None, None, None, None, None,
+    /*  45 */ None, None, None, None, Some(0x12),
+    /*  50 */ None, Some(0x19), Some(0x1a), Some(0x1b), Some(0x1e),
+    /*  55 */ Some(0x1d), Some(0x07), Some(0x1f), None, None,
+    /*  60 */ None, None, None, None, None,
+    /*  65 */ None, None, None, None, None,
+    /*  70 */ None, None, None, None, None,
+    /*  75 */ None, None, None, None, None,
+    /*  80 */ None, None, None, None, None,
+    /*  85 */ None, None, None, None, None,
+    /*  90 */ None, None, None, None, None,
+    /*  95 */ None, None, Some(0x18), Some(0x01), Some(0x0c),
+    /* 100 */ Some(0x03), Some(0x08), Some(0x05), Some(0x06), Some(0x1c),
+    /* 105 */ Some(0x15), Some(0x09), Some(0x0a), None, Some(0x0b),
+    /* 110 */ Some(0x02), Some(0x10), Some(0x0d), Some(0x0e), Some(0x04),
+    /* 115 */ Some(0x16), Some(0x11), Some(0x13), None, Some(0x14),
+    /* 120 */ Some(0x0f), Some(0x00), Some(0x17), None, None,
+    /* 125 */ None, None, None, None, None,
+    /* 130 */ None, None, None, None, None,
+    /* 135 */ None, None, None, None, None,
+    /* 140 */ None, None, None, None, None,
+    /* 145 */ None, None, None, None, None,
+    /* 150 */ None, None, None, None, None,
+    /* 155 */ None, None, None, None, None,
+    /* 160 */ None, None, None, None, None,
+    /* 165 */ None, None, None, None, None,
+    /* 170 */ None, None, None, None, None,
+    /* 175 */ None, None, None, None, None,
+    /* 180 */ None, None, None, None, None,
+    /* 185 */ None, None, None, None, None,
+    /* 190 */ None, None, None, None, None,
+    /* 195 */ None, None, None, None, None,
+    /* 200 */ None, None, None, None, None,
+    /* 205 */ None, None, None, None, None,
+    /* 210 */ None, None, None, None, None,
+    /* 215 */ None, None, None, None, None,
+    /* 220 */ None, None, None, None, None,
+    /* 225 */ None, None, None, None, None,
+    /* 230 */ None, None, None, None, None,
+    /* 235 */ None, None, None, None, None,
+    /* 240 */ None, None, None, None, None,
+    /* 245 */ None, None, None, None, None,
+    /* 250 */ None, None, None, None, None,
+    /* 255 */ None
 ];
 
 #[inline]
-fn value_of_digit(digit: u8) -> Result<u8, &'static str> {
+fn value_of_digit(digit: u8) -> Option<u8> {
     CONVERSION_TABLE[digit as usize]
 }
 
-/// Decode first N `bits` of given zbase32 encoded data
+/// Decode first N `bits` of given zbase32 encoded data into a preallocated buffer
+///
+/// Returns the number of bytes written to `out`. Unlike `decode`, this does not
+/// allocate; it writes directly into the caller-supplied slice, which lets
+/// callers reuse a buffer across calls instead of paying for a fresh `Vec`
+/// every time.
 ///
-/// # Panic
+/// # Errors
 ///
-/// Panics if `zbase32` decoded is shorter than N `bits`.
+/// Returns an error if `zbase32` doesn't contain enough bits, or if `out` is
+/// too short to hold the decoded bytes.
 ///
 /// # Examples
 ///
 /// ```
 /// use zbase32;
 ///
-/// assert_eq!(zbase32::decode(b"o", 1).unwrap(), &[0x80]);
+/// let mut out = [0; 1];
+/// assert_eq!(zbase32::decode_slice(b"o", 1, &mut out).unwrap(), 1);
+/// assert_eq!(&out, &[0x80]);
 /// ```
-pub fn decode(zbase32: &[u8], bits: u64) -> Result<Vec<u8>, &'static str> {
-    assert!(zbase32.len() as u64 * 5 >= bits, "zbase32 slice too short");
+pub fn decode_slice(zbase32: &[u8], bits: u64, out: &mut [u8]) -> Result<usize, Error> {
+    let available = zbase32.len() as u64 * 5;
+    if available < bits {
+        return Err(Error::InputTooShort {
+            needed: bits,
+            got: available,
+        });
+    }
     let capacity = if bits % 8 == 0 {
         bits / 8
     } else {
         bits / 8 + 1
     } as usize;
-    let mut result = Vec::with_capacity(capacity);
+    if out.len() < capacity {
+        return Err(Error::BufferTooShort {
+            needed: capacity,
+            got: out.len(),
+        });
+    }
+
+    let mut len = 0;
+    let mut bits_remaining = bits;
+    let mut buffer_size: u8 = 0;
+    let mut buffer: u16 = !0;
+    for (index, digit) in zbase32.iter().enumerate() {
+        let value = value_of_digit(*digit).ok_or(Error::InvalidDigit {
+            index,
+            byte: *digit,
+        })?;
+        buffer = (buffer << 5) | u16::from(value);
+        buffer_size += 5;
+        if bits_remaining < 8 && u64::from(buffer_size) >= bits_remaining {
+            break;
+        }
+        if buffer_size >= 8 {
+            let byte = (buffer >> (buffer_size - 8)) as u8;
+            out[len] = byte;
+            len += 1;
+            bits_remaining -= 8;
+            buffer_size -= 8;
+        }
+    }
+    if bits_remaining > 0 {
+        let trim_right = buffer_size - bits_remaining as u8;
+        buffer >>= trim_right;
+        buffer_size -= trim_right;
+        let byte = (buffer << (8_u8 - buffer_size)) as u8;
+        out[len] = byte;
+        len += 1;
+    }
+    debug_assert_eq!(capacity, len);
+    Ok(len)
+}
+
+/// Decode first N `bits` of given zbase32 encoded data in place.
+///
+/// Reads zbase32 symbols from `buf` and overwrites the low portion of the
+/// same slice with the decoded bytes, returning the written prefix. This is
+/// safe because decoding always consumes a symbol ahead of the position it
+/// next writes to (5 bits in per symbol vs. 8 bits out per byte), so the
+/// write never catches up to the read. Useful for callers that own a mutable
+/// encoded buffer and want to avoid the output allocation `decode` makes.
+///
+/// # Errors
+///
+/// Returns an error if `buf` doesn't contain enough bits.
+///
+/// # Examples
+///
+/// ```
+/// use zbase32;
+///
+/// let mut buf = *b"o";
+/// assert_eq!(zbase32::decode_in_place(&mut buf, 1).unwrap(), &[0x80]);
+/// ```
+pub fn decode_in_place(buf: &mut [u8], bits: u64) -> Result<&[u8], Error> {
+    let available = buf.len() as u64 * 5;
+    if available < bits {
+        return Err(Error::InputTooShort {
+            needed: bits,
+            got: available,
+        });
+    }
 
+    let mut len = 0;
     let mut bits_remaining = bits;
     let mut buffer_size: u8 = 0;
     let mut buffer: u16 = !0;
-    for digit in zbase32 {
-        let value = value_of_digit(*digit)?;
+    for index in 0..buf.len() {
+        let digit = buf[index];
+        let value = value_of_digit(digit).ok_or(Error::InvalidDigit {
+            index,
+            byte: digit,
+        })?;
         buffer = (buffer << 5) | u16::from(value);
         buffer_size += 5;
         if bits_remaining < 8 && u64::from(buffer_size) >= bits_remaining {
@@ -131,7 +247,8 @@ pub fn decode(zbase32: &[u8], bits: u64) -> Result<Vec<u8>, &'static str> {
         }
         if buffer_size >= 8 {
             let byte = (buffer >> (buffer_size - 8)) as u8;
-            result.push(byte);
+            buf[len] = byte;
+            len += 1;
             bits_remaining -= 8;
             buffer_size -= 8;
         }
@@ -141,9 +258,36 @@ pub fn decode(zbase32: &[u8], bits: u64) -> Result<Vec<u8>, &'static str> {
         buffer >>= trim_right;
         buffer_size -= trim_right;
         let byte = (buffer << (8_u8 - buffer_size)) as u8;
-        result.push(byte);
+        buf[len] = byte;
+        len += 1;
     }
-    debug_assert_eq!(capacity, result.len());
+    Ok(&buf[..len])
+}
+
+/// Decode first N `bits` of given zbase32 encoded data
+///
+/// # Errors
+///
+/// Returns an error if `zbase32` doesn't contain enough bits, or contains an
+/// invalid digit.
+///
+/// # Examples
+///
+/// ```
+/// use zbase32;
+///
+/// assert_eq!(zbase32::decode(b"o", 1).unwrap(), &[0x80]);
+/// ```
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub fn decode(zbase32: &[u8], bits: u64) -> Result<Vec<u8>, Error> {
+    let capacity = if bits % 8 == 0 {
+        bits / 8
+    } else {
+        bits / 8 + 1
+    } as usize;
+    let mut result = vec![0; capacity];
+    let len = decode_slice(zbase32, bits, &mut result)?;
+    debug_assert_eq!(capacity, len);
     Ok(result)
 }
 
@@ -162,17 +306,38 @@ pub fn decode(zbase32: &[u8], bits: u64) -> Result<Vec<u8>, &'static str> {
 ///
 /// assert_eq!(zbase32::decode_full_bytes(b"qb1ze3m1").unwrap(), b"peter");
 /// ```
-#[inline]
-pub fn decode_full_bytes(zbase: &[u8]) -> Result<Vec<u8>, &'static str> {
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub fn decode_full_bytes(zbase: &[u8]) -> Result<Vec<u8>, Error> {
+    #[cfg(all(feature = "simd", feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+    {
+        if zbase.len() >= simd::THRESHOLD && is_x86_feature_detected!("avx2") {
+            let consumed = zbase.len() / simd::SYMBOLS_PER_STEP * simd::SYMBOLS_PER_STEP;
+            if consumed > 0 {
+                let mut result = vec![0; consumed / 8 * 5];
+                let written = unsafe { simd::decode_avx2(&zbase[..consumed], &mut result)? };
+                debug_assert_eq!(written, result.len());
+                result.extend(decode_full_bytes(&zbase[consumed..]).map_err(|e| match e {
+                    Error::InvalidDigit { index, byte } => Error::InvalidDigit {
+                        index: index + consumed,
+                        byte,
+                    },
+                    e => e,
+                })?);
+                return Ok(result);
+            }
+        }
+    }
+
     let size = zbase.len() as u64 * 5;
     decode(zbase, size / 8 * 8)
 }
 
 /// Decode first N `bits` of given zbase32 encoded string
 ///
-/// # Panic
+/// # Errors
 ///
-/// Panics if `zbase32` decoded is shorter than N `bits`.
+/// Returns an error if `zbase32` doesn't contain enough bits, or contains an
+/// invalid digit.
 ///
 /// # Examples
 ///
@@ -181,8 +346,9 @@ pub fn decode_full_bytes(zbase: &[u8]) -> Result<Vec<u8>, &'static str> {
 ///
 /// assert_eq!(zbase32::decode_str("o", 1).unwrap(), &[0x80]);
 /// ```
+#[cfg(any(feature = "std", feature = "alloc"))]
 #[inline]
-pub fn decode_str(zbase32: &str, bits: u64) -> Result<Vec<u8>, &'static str> {
+pub fn decode_str(zbase32: &str, bits: u64) -> Result<Vec<u8>, Error> {
     decode(zbase32.as_bytes(), bits)
 }
 
@@ -197,34 +363,22 @@ pub fn decode_str(zbase32: &str, bits: u64) -> Result<Vec<u8>, &'static str> {
 ///
 /// assert_eq!(zbase32::decode_full_bytes_str("qb1ze3m1").unwrap(), b"peter");
 /// ```
+#[cfg(any(feature = "std", feature = "alloc"))]
 #[inline]
-pub fn decode_full_bytes_str(zbase32: &str) -> Result<Vec<u8>, &'static str> {
+pub fn decode_full_bytes_str(zbase32: &str) -> Result<Vec<u8>, Error> {
     decode_full_bytes(zbase32.as_bytes())
 }
 
-/// Encode first N `bits` with zbase32.
-///
-/// # Panics
-///
-/// Panics if `data` is shorter than N `bits`.
-///
-/// # Examples
-///
-/// ```
-/// use zbase32;
+/// Runs the zbase32 encode bit-packing loop over `data`, calling `emit` with
+/// each output symbol in order. Returns the number of symbols emitted.
 ///
-/// assert_eq!(zbase32::encode(b"testdata", 64), "qt1zg7drcf4gn");
-/// ```
-///
-pub fn encode(data: &[u8], bits: u64) -> String {
-    assert!(data.len() as u64 * 8 >= bits, "slice too short");
-    let capacity = if bits % 5 == 0 {
-        bits / 5
-    } else {
-        bits / 5 + 1
-    } as usize;
-    let mut result = Vec::with_capacity(capacity);
-
+/// Shared by `encode_slice` (which writes symbols into a byte slice) and the
+/// `Display` adapter (which writes them straight into a `fmt::Write` sink),
+/// so the bit-packing logic only needs to be correct in one place. Callers
+/// are expected to have already checked that `data` holds at least `bits`
+/// bits.
+fn encode_with<F: FnMut(u8)>(data: &[u8], bits: u64, mut emit: F) -> usize {
+    let mut len = 0;
     let mut bits_remaining = bits;
     let mut bit_offset: u8 = 16;
     let mut remaining = data;
@@ -241,13 +395,92 @@ pub fn encode(data: &[u8], bits: u64) -> String {
 
         let unused_bits = 5_u64.saturating_sub(bits_remaining);
         let index = (buffer >> (unused_bits as u8 + 16 - 5 - bit_offset) << unused_bits) & 0x1f;
-        result.push(ALPHABET[index as usize]);
+        emit(ALPHABET[index as usize]);
+        len += 1;
 
         bit_offset += 5;
         bits_remaining -= 5 - unused_bits;
     }
 
-    debug_assert_eq!(capacity, result.len());
+    len
+}
+
+/// Encode first N `bits` with zbase32 into a preallocated buffer
+///
+/// Returns the number of bytes written to `out`. Unlike `encode`, this does
+/// not allocate; it writes the encoded symbols directly into the
+/// caller-supplied slice, which lets callers reuse a buffer across calls
+/// instead of paying for a fresh `String` every time.
+///
+/// # Errors
+///
+/// Returns an error if `data` doesn't contain enough bits, or if `out` is too
+/// short to hold the encoded symbols.
+///
+/// # Examples
+///
+/// ```
+/// use zbase32;
+///
+/// let mut out = [0; 13];
+/// assert_eq!(zbase32::encode_slice(b"testdata", 64, &mut out).unwrap(), 13);
+/// assert_eq!(&out, b"qt1zg7drcf4gn");
+/// ```
+pub fn encode_slice(data: &[u8], bits: u64, out: &mut [u8]) -> Result<usize, Error> {
+    let available = data.len() as u64 * 8;
+    if available < bits {
+        return Err(Error::InputTooShort {
+            needed: bits,
+            got: available,
+        });
+    }
+    let capacity = if bits % 5 == 0 {
+        bits / 5
+    } else {
+        bits / 5 + 1
+    } as usize;
+    if out.len() < capacity {
+        return Err(Error::BufferTooShort {
+            needed: capacity,
+            got: out.len(),
+        });
+    }
+
+    let mut len = 0;
+    encode_with(data, bits, |symbol| {
+        out[len] = symbol;
+        len += 1;
+    });
+
+    debug_assert_eq!(capacity, len);
+    Ok(len)
+}
+
+/// Encode first N `bits` with zbase32.
+///
+/// # Panics
+///
+/// Panics if `data` is shorter than N `bits`.
+///
+/// # Examples
+///
+/// ```
+/// use zbase32;
+///
+/// assert_eq!(zbase32::encode(b"testdata", 64), "qt1zg7drcf4gn");
+/// ```
+///
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub fn encode(data: &[u8], bits: u64) -> String {
+    assert!(data.len() as u64 * 8 >= bits, "slice too short");
+    let capacity = if bits % 5 == 0 {
+        bits / 5
+    } else {
+        bits / 5 + 1
+    } as usize;
+    let mut result = vec![0; capacity];
+    let len = encode_slice(data, bits, &mut result).expect("encode_slice: buffer too short");
+    debug_assert_eq!(capacity, len);
     unsafe { String::from_utf8_unchecked(result) }
 }
 
@@ -264,8 +497,22 @@ pub fn encode(data: &[u8], bits: u64) -> String {
 /// assert_eq!(zbase32::encode_full_bytes(data.as_bytes()),
 ///            "jj4zg7bycfznyam1cjwzehubqjh1yh5fp34gk5udcwzy");
 /// ```
-#[inline]
+#[cfg(any(feature = "std", feature = "alloc"))]
 pub fn encode_full_bytes(data: &[u8]) -> String {
+    #[cfg(all(feature = "simd", feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+    {
+        if data.len() >= simd::THRESHOLD && is_x86_feature_detected!("avx2") {
+            let consumed = data.len() / simd::BYTES_PER_STEP * simd::BYTES_PER_STEP;
+            if consumed > 0 {
+                let mut result = vec![0; consumed / 5 * 8];
+                let written = unsafe { simd::encode_avx2(&data[..consumed], &mut result) };
+                debug_assert_eq!(written, consumed);
+                result.extend(encode_full_bytes(&data[consumed..]).into_bytes());
+                return unsafe { String::from_utf8_unchecked(result) };
+            }
+        }
+    }
+
     encode(data, data.len() as u64 * 8)
 }
 
@@ -280,7 +527,7 @@ pub fn encode_full_bytes(data: &[u8]) -> String {
 /// assert!(!zbase32::validate(b"A"));
 /// ```
 pub fn validate(data: &[u8]) -> bool {
-    data.iter().all(|i| value_of_digit(*i).is_ok())
+    data.iter().all(|i| value_of_digit(*i).is_some())
 }
 
 /// Check if `data` is valid zbase32 encoded string
@@ -365,6 +612,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_decode_invalid_digit_position() {
+        let err = decode_full_bytes(b"yyAyy").unwrap_err();
+        assert_eq!(err, Error::InvalidDigit { index: 2, byte: b'A' });
+    }
+
+    #[test]
+    fn test_decode_slice_buffer_too_short_error() {
+        let mut out = [0; 2];
+        let err = decode_slice(b"6n9hq", 24, &mut out).unwrap_err();
+        assert_eq!(err, Error::BufferTooShort { needed: 3, got: 2 });
+    }
+
     #[test]
     fn test_decode_superfluous_bits() {
         assert_eq!(decode(b"999", 1).unwrap(), &[0x80]);
@@ -374,9 +634,46 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "zbase32 slice too short")]
     fn test_decode_short_slice() {
-        decode(b"oyoy", 4 * 5 + 1).unwrap();
+        let err = decode(b"oyoy", 4 * 5 + 1).unwrap_err();
+        assert_eq!(err, Error::InputTooShort { needed: 21, got: 20 });
+    }
+
+    #[test]
+    fn test_decode_slice() {
+        for &(bits, zbase32, data) in TEST_DATA {
+            let mut out = vec![0; data.len()];
+            assert_eq!(decode_slice(zbase32.as_bytes(), bits, &mut out).unwrap(), data.len());
+            assert_eq!(&out[..], data);
+        }
+    }
+
+    #[test]
+    fn test_decode_slice_buffer_too_short() {
+        let mut out = [0; 2];
+        assert!(decode_slice(b"6n9hq", 24, &mut out).is_err());
+    }
+
+    #[test]
+    fn test_decode_in_place() {
+        for &(bits, zbase32, data) in TEST_DATA {
+            let mut buf = zbase32.as_bytes().to_vec();
+            assert_eq!(&decode_in_place(&mut buf, bits).unwrap()[..], data);
+        }
+    }
+
+    #[test]
+    fn test_decode_in_place_invalid_digit() {
+        let mut buf = *b"yyAyy";
+        let err = decode_in_place(&mut buf, 25).unwrap_err();
+        assert_eq!(err, Error::InvalidDigit { index: 2, byte: b'A' });
+    }
+
+    #[test]
+    fn test_decode_in_place_short_slice() {
+        let mut buf = *b"oyoy";
+        let err = decode_in_place(&mut buf, 4 * 5 + 1).unwrap_err();
+        assert_eq!(err, Error::InputTooShort { needed: 21, got: 20 });
     }
 
     #[test]
@@ -386,6 +683,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_encode_slice() {
+        for &(bits, zbase32, data) in TEST_DATA {
+            let mut out = vec![0; zbase32.len()];
+            assert_eq!(encode_slice(data, bits, &mut out).unwrap(), zbase32.len());
+            assert_eq!(&out[..], zbase32.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_encode_slice_buffer_too_short() {
+        let mut out = [0; 4];
+        assert!(encode_slice(b"4t7ye", 24, &mut out).is_err());
+    }
+
+    #[test]
+    fn test_encode_slice_short_slice() {
+        let mut out = [0; 5];
+        let err = encode_slice(b"1234", 4 * 8 + 1, &mut out).unwrap_err();
+        assert_eq!(err, Error::InputTooShort { needed: 33, got: 32 });
+    }
+
     #[test]
     fn test_encode_superfluous_bits() {
         assert_eq!(encode(&[0xff, 0xff], 1), "o");
@@ -430,7 +749,7 @@ mod tests {
                 assert_eq!(encode(&decode(bytes, 5).unwrap(), 5).as_bytes(), bytes);
                 assert!(validate(bytes));
             } else {
-                assert!(value_of_digit(char).is_err());
+                assert!(value_of_digit(char).is_none());
                 assert!(decode_full_bytes(bytes).is_err());
                 assert!(!validate(bytes));
             }
@@ -0,0 +1,114 @@
+//! Streaming zbase32 decoding for `Read`ers.
+
+use std::cmp;
+use std::io::{self, Read};
+
+use decode_full_bytes;
+
+/// A `Read` adapter that pulls zbase32 symbols from an inner reader and
+/// yields the decoded bytes.
+///
+/// Symbols are consumed 8 at a time (the group that decodes to exactly 5
+/// bytes with no leftover bits), so multi-gigabyte streams can be decoded
+/// without materializing the whole input or the whole output at once. The
+/// final, possibly shorter, group is decoded the same way `decode_full_bytes`
+/// would decode it: truncated to the last full byte boundary. Invalid digits
+/// are surfaced as an `io::Error` of kind `InvalidData`.
+pub struct Decoder<R: Read> {
+    reader: R,
+    buffer: Vec<u8>,
+    pos: usize,
+    done: bool,
+}
+
+impl<R: Read> Decoder<R> {
+    /// Create a new decoder pulling zbase32 symbols from `reader`.
+    pub fn new(reader: R) -> Decoder<R> {
+        Decoder {
+            reader,
+            buffer: Vec::new(),
+            pos: 0,
+            done: false,
+        }
+    }
+
+    fn fill_buffer(&mut self) -> io::Result<()> {
+        if self.pos < self.buffer.len() || self.done {
+            return Ok(());
+        }
+
+        let mut symbols = [0; 8];
+        let mut read = 0;
+        while read < symbols.len() {
+            match self.reader.read(&mut symbols[read..])? {
+                0 => break,
+                n => read += n,
+            }
+        }
+
+        if read < symbols.len() {
+            self.done = true;
+        }
+        if read == 0 {
+            return Ok(());
+        }
+
+        self.buffer = decode_full_bytes(&symbols[..read])
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        self.pos = 0;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for Decoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        self.fill_buffer()?;
+
+        let available = &self.buffer[self.pos..];
+        let n = cmp::min(buf.len(), available.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decoder_one_shot() {
+        let mut decoder = Decoder::new(&b"qt1zg7drcf4gn"[..]);
+        let mut out = Vec::new();
+        decoder.read_to_end(&mut out).unwrap();
+        assert_eq!(out, b"testdata");
+    }
+
+    #[test]
+    fn test_decoder_byte_at_a_time() {
+        let mut decoder = Decoder::new(&b"qt1zg7drcf4gn"[..]);
+        let mut out = Vec::new();
+        let mut byte = [0; 1];
+        loop {
+            match decoder.read(&mut byte).unwrap() {
+                0 => break,
+                n => out.extend_from_slice(&byte[..n]),
+            }
+        }
+        assert_eq!(out, b"testdata");
+    }
+
+    #[test]
+    fn test_decoder_invalid_digit() {
+        let mut decoder = Decoder::new(&b"0000"[..]);
+        let mut out = Vec::new();
+        assert_eq!(
+            decoder.read_to_end(&mut out).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+}
@@ -0,0 +1,110 @@
+//! Streaming zbase32 encoding for `Write`rs.
+
+use std::cmp;
+use std::io::{self, Write};
+
+use encode_full_bytes;
+
+/// A `Write` adapter that zbase32-encodes bytes as they're written, forwarding
+/// the encoded symbols to an inner writer.
+///
+/// Input is buffered internally until 5 full bytes (which encode to exactly
+/// 8 symbols with no padding) are available, so multi-gigabyte streams can be
+/// encoded without materializing the whole input or the whole output at
+/// once. Call `finish` when done to flush any buffered trailing bytes.
+pub struct Encoder<W: Write> {
+    writer: W,
+    buffer: [u8; 5],
+    buffer_len: usize,
+}
+
+impl<W: Write> Encoder<W> {
+    /// Create a new encoder wrapping `writer`.
+    pub fn new(writer: W) -> Encoder<W> {
+        Encoder {
+            writer,
+            buffer: [0; 5],
+            buffer_len: 0,
+        }
+    }
+
+    /// Encode any buffered trailing bytes and return the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.flush_buffer()?;
+        Ok(self.writer)
+    }
+
+    fn flush_buffer(&mut self) -> io::Result<()> {
+        if self.buffer_len > 0 {
+            let encoded = encode_full_bytes(&self.buffer[..self.buffer_len]);
+            self.writer.write_all(encoded.as_bytes())?;
+            self.buffer_len = 0;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let total = buf.len();
+        let mut input = buf;
+
+        while self.buffer_len > 0 && !input.is_empty() {
+            let n = cmp::min(5 - self.buffer_len, input.len());
+            self.buffer[self.buffer_len..self.buffer_len + n].copy_from_slice(&input[..n]);
+            self.buffer_len += n;
+            input = &input[n..];
+            if self.buffer_len == 5 {
+                self.flush_buffer()?;
+            }
+        }
+
+        while input.len() >= 5 {
+            let (chunk, rest) = input.split_at(5);
+            let encoded = encode_full_bytes(chunk);
+            self.writer.write_all(encoded.as_bytes())?;
+            input = rest;
+        }
+
+        if !input.is_empty() {
+            self.buffer[..input.len()].copy_from_slice(input);
+            self.buffer_len = input.len();
+        }
+
+        Ok(total)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encoder_one_shot() {
+        let mut encoder = Encoder::new(Vec::new());
+        encoder.write_all(b"testdata").unwrap();
+        let out = encoder.finish().unwrap();
+        assert_eq!(out, b"qt1zg7drcf4gn");
+    }
+
+    #[test]
+    fn test_encoder_byte_at_a_time() {
+        let mut encoder = Encoder::new(Vec::new());
+        for byte in b"testdata" {
+            encoder.write_all(&[*byte]).unwrap();
+        }
+        let out = encoder.finish().unwrap();
+        assert_eq!(out, b"qt1zg7drcf4gn");
+    }
+
+    #[test]
+    fn test_encoder_empty() {
+        let encoder = Encoder::new(Vec::new());
+        let out = encoder.finish().unwrap();
+        assert!(out.is_empty());
+    }
+}